@@ -0,0 +1,19 @@
+use std::fmt;
+
+/// A numeric literal, as scanned by the lexer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+  Int(u64),
+  Float(f64),
+  Ratio { num: u64, den: u64 },
+}
+
+impl fmt::Display for Number {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Int(n) => write!(f, "{}", n),
+      Self::Float(x) => write!(f, "{:?}", x),
+      Self::Ratio { num, den } => write!(f, "{}/{}", num, den),
+    }
+  }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+/// A deduplicated identifier. Cheap to copy and compare, unlike the `String`
+/// it stands in for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sym(u32);
+
+/// Deduplicates identifier strings into compact, copyable [`Sym`]s.
+///
+/// Each distinct name is stored once in `strings`; `lookup` maps back to the
+/// `Sym` that was handed out for it so re-interning the same name is O(1).
+#[derive(Debug, Default)]
+pub struct Interner {
+  strings: Vec<String>,
+  lookup: HashMap<String, Sym>,
+}
+
+impl Interner {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Interns `name`, returning its existing `Sym` if already seen.
+  pub fn intern(&mut self, name: &str) -> Sym {
+    if let Some(&sym) = self.lookup.get(name) {
+      return sym;
+    }
+    let sym = Sym(self.strings.len() as u32);
+    self.strings.push(name.to_string());
+    self.lookup.insert(name.to_string(), sym);
+    sym
+  }
+
+  /// Resolves a `Sym` back to the text it was interned from.
+  pub fn resolve(&self, sym: Sym) -> &str {
+    &self.strings[sym.0 as usize]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn interning_same_string_twice_yields_same_sym() {
+    let mut interner = Interner::new();
+    let a = interner.intern("foo");
+    let b = interner.intern("foo");
+    assert_eq!(a, b);
+  }
+
+  #[test]
+  fn interning_different_strings_yields_different_syms() {
+    let mut interner = Interner::new();
+    let a = interner.intern("foo");
+    let b = interner.intern("bar");
+    assert_ne!(a, b);
+  }
+
+  #[test]
+  fn resolve_round_trips_the_original_text() {
+    let mut interner = Interner::new();
+    let foo = interner.intern("foo");
+    let bar = interner.intern("bar");
+    assert_eq!(interner.resolve(foo), "foo");
+    assert_eq!(interner.resolve(bar), "bar");
+  }
+}
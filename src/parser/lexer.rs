@@ -1,13 +1,95 @@
 use logos::{FilterResult, Lexer, Logos};
 use std::fmt;
+use std::ops::Range;
 
 use crate::ast::Number;
+use crate::parser::interner::{Interner, Sym};
 
+/// A byte offset range into the original source string.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+  pub start: u32,
+  pub end: u32,
+}
+
+impl From<Range<usize>> for Span {
+  fn from(range: Range<usize>) -> Self {
+    Self { start: range.start as u32, end: range.end as u32 }
+  }
+}
+
+/// Wraps a value with the span of source text it was produced from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Located<T> {
+  pub item: T,
+  pub span: Span,
+}
+
+/// Lexes `input` into a sequence of tokens borrowing from it, each carrying
+/// the span of source it was scanned from. Invalid characters are reported
+/// as `Token::Error` rather than stopping the lex, so a single pass surfaces
+/// every error. Comments are discarded; use [`LexerBuilder`] to keep them.
+pub fn lex(input: &str) -> Vec<Located<Token<'_>>> {
+  LexerBuilder::new().lex(input)
+}
+
+/// Configures and runs the lexer.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LexerBuilder {
+  preserve_comments: bool,
+}
+
+impl LexerBuilder {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// When set, comments are emitted as `Token::Comment` instead of being
+  /// discarded, so a formatter can reprint them or a doc tool can harvest
+  /// them. Off by default, which is what the parser wants.
+  pub fn preserve_comments(mut self, preserve_comments: bool) -> Self {
+    self.preserve_comments = preserve_comments;
+    self
+  }
+
+  pub fn lex(self, input: &str) -> Vec<Located<Token<'_>>> {
+    let mut lexer = Token::lexer(input);
+    lexer.extras.preserve_comments = self.preserve_comments;
+    let mut tokens = Vec::new();
+    while let Some(result) = lexer.next() {
+      let span = Span::from(lexer.span());
+      let item = match result {
+        Ok(token) => token,
+        // The auto-generated "no pattern matched" error carries no span,
+        // since logos constructs it via `Default` with no lexer in scope.
+        // Patch it in here, where `lexer.span()` is available.
+        Err(LexingError::InvalidCharacter(_)) => Token::Error(LexingError::InvalidCharacter(span)),
+        Err(err) => Token::Error(err),
+      };
+      tokens.push(Located { item, span });
+    }
+    tokens
+  }
+}
+
+/// Per-lex state threaded through logos via `#[logos(extras)]`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LexerState {
+  preserve_comments: bool,
+}
+
+/// A scanned token borrowing directly from the source it was lexed from,
+/// wherever that avoids an allocation (names, comments). Call
+/// [`Token::into_owned`] to detach it from the source's lifetime.
 #[derive(Logos, Debug, PartialEq, Clone)]
 #[logos(error=LexingError)]
-pub enum Token {
-  #[regex("[_a-zA-Z][_a-zA-Z0-9]*", |lex| lex.slice().parse().ok())]
-  Name(String),
+#[logos(extras=LexerState)]
+pub enum Token<'src> {
+  #[regex("[_a-zA-Z][_a-zA-Z0-9]*", |lex| lex.slice())]
+  Name(&'src str),
+
+  #[token("\"", string_literal)]
+  Str(String),
 
   #[regex("@|λ")]
   Lambda,
@@ -21,7 +103,10 @@ pub enum Token {
   #[token("=")]
   Equals,
 
-  #[regex("[0-9]+", |lex| lex.slice().parse().map(Number).ok())]
+  #[regex(r"[0-9]+\.[0-9]+([eE][+-]?[0-9]+)?", |lex| lex.slice().parse().map(Number::Float).ok())]
+  #[regex(r"[0-9]+[eE][+-]?[0-9]+", |lex| lex.slice().parse().map(Number::Float).ok())]
+  #[regex(r"[0-9]+/[0-9]+", number_ratio)]
+  #[regex("[0-9]+", |lex| lex.slice().parse().map(Number::Int).map_err(|_| LexingError::InvalidNumber(Span::from(lex.span()))))]
   Number(Number),
 
   #[token("+")]
@@ -84,11 +169,9 @@ pub enum Token {
   #[token("\n")]
   NewLine,
 
-  #[regex("//.*", logos::skip)]
-  SingleLineComment,
-
-  #[token("/*", comment)]
-  MultiLineComment,
+  #[regex("//.*", single_line_comment)]
+  #[token("/*", multi_line_comment)]
+  Comment(&'src str),
 
   #[regex(r"[ \t\f\r]+", logos::skip)]
   Whitespace,
@@ -96,12 +179,95 @@ pub enum Token {
   Error(LexingError),
 }
 
-#[derive(Default, Debug, PartialEq, Clone)]
+/// The owned counterpart of [`Token`], for callers (e.g. a parser that
+/// holds onto tokens) that need them to outlive the source buffer. Obtained
+/// via [`Token::into_owned`], which also interns `Name`s along the way.
+#[derive(Debug, PartialEq, Clone)]
+pub enum OwnedToken {
+  Name(Sym),
+  Str(String),
+  Lambda,
+  Let,
+  Dup,
+  Equals,
+  Number(Number),
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Mod,
+  And,
+  Or,
+  Xor,
+  Shl,
+  Shr,
+  Ltn,
+  Lte,
+  Gtn,
+  Gte,
+  EqualsEquals,
+  NotEquals,
+  Semicolon,
+  LParen,
+  RParen,
+  NewLine,
+  Comment(String),
+  Whitespace,
+  Error(LexingError),
+}
+
+impl<'src> Token<'src> {
+  /// Copies any borrowed text out of this token, interning `Name`s through
+  /// `interner` so the result no longer depends on the source buffer.
+  pub fn into_owned(self, interner: &mut Interner) -> OwnedToken {
+    match self {
+      Self::Name(s) => OwnedToken::Name(interner.intern(s)),
+      Self::Str(s) => OwnedToken::Str(s),
+      Self::Lambda => OwnedToken::Lambda,
+      Self::Let => OwnedToken::Let,
+      Self::Dup => OwnedToken::Dup,
+      Self::Equals => OwnedToken::Equals,
+      Self::Number(n) => OwnedToken::Number(n),
+      Self::Add => OwnedToken::Add,
+      Self::Sub => OwnedToken::Sub,
+      Self::Mul => OwnedToken::Mul,
+      Self::Div => OwnedToken::Div,
+      Self::Mod => OwnedToken::Mod,
+      Self::And => OwnedToken::And,
+      Self::Or => OwnedToken::Or,
+      Self::Xor => OwnedToken::Xor,
+      Self::Shl => OwnedToken::Shl,
+      Self::Shr => OwnedToken::Shr,
+      Self::Ltn => OwnedToken::Ltn,
+      Self::Lte => OwnedToken::Lte,
+      Self::Gtn => OwnedToken::Gtn,
+      Self::Gte => OwnedToken::Gte,
+      Self::EqualsEquals => OwnedToken::EqualsEquals,
+      Self::NotEquals => OwnedToken::NotEquals,
+      Self::Semicolon => OwnedToken::Semicolon,
+      Self::LParen => OwnedToken::LParen,
+      Self::RParen => OwnedToken::RParen,
+      Self::NewLine => OwnedToken::NewLine,
+      Self::Comment(s) => OwnedToken::Comment(s.to_string()),
+      Self::Whitespace => OwnedToken::Whitespace,
+      Self::Error(e) => OwnedToken::Error(e),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum LexingError {
-  UnclosedComment,
+  UnclosedComment(Span),
+  UnterminatedString(Span),
+  InvalidNumber(Span),
+  InvalidCharacter(Span),
+}
 
-  #[default]
-  InvalidCharacter,
+impl Default for LexingError {
+  fn default() -> Self {
+    // Filled in with the real span by `lex` once a `Lexer` is in scope.
+    Self::InvalidCharacter(Span::default())
+  }
 }
 
 // Lexer for nested multi-line comments
@@ -117,7 +283,18 @@ pub enum MultiLineComment {
   Other,
 }
 
-fn comment(lexer: &mut Lexer<'_, Token>) -> FilterResult<(), LexingError> {
+fn single_line_comment<'src>(lexer: &mut Lexer<'src, Token<'src>>) -> FilterResult<&'src str, LexingError> {
+  if lexer.extras.preserve_comments {
+    FilterResult::Emit(lexer.slice()[2..].trim())
+  } else {
+    FilterResult::Skip
+  }
+}
+
+fn multi_line_comment<'src>(lexer: &mut Lexer<'src, Token<'src>>) -> FilterResult<&'src str, LexingError> {
+  // Record where the outermost `/*` began, so an unclosed nested comment
+  // points at its true start rather than wherever scanning gave up.
+  let comment_start = lexer.span().start as u32;
   let start = lexer.remainder();
   let mut comment = MultiLineComment::lexer(start);
   let mut depth = 1; // Already matched an Open token, so count it
@@ -130,28 +307,104 @@ fn comment(lexer: &mut Lexer<'_, Token>) -> FilterResult<(), LexingError> {
         Err(()) => unreachable!(),
       }
     } else {
-      // Unclosed comment
-      return FilterResult::Error(LexingError::UnclosedComment);
+      // Unclosed comment: it necessarily runs to the end of the source.
+      // Bump the outer lexer to EOF too, so scanning actually stops instead
+      // of resuming inside the unterminated comment body.
+      let comment_end = lexer.source().len() as u32;
+      lexer.bump(lexer.remainder().len());
+      return FilterResult::Error(LexingError::UnclosedComment(Span { start: comment_start, end: comment_end }));
     }
     if depth <= 0 {
       break;
     }
   }
   let end = comment.remainder();
-  let span = (end as *const str as *const () as usize) - (start as *const str as *const () as usize);
-  lexer.bump(span);
-  FilterResult::Skip
+  let consumed = (end as *const str as *const () as usize) - (start as *const str as *const () as usize);
+  // The body between the outermost `/*` and its matching `*/`, delimiters excluded.
+  let body = &start[..consumed - "*/".len()];
+  lexer.bump(consumed);
+  if lexer.extras.preserve_comments {
+    FilterResult::Emit(body)
+  } else {
+    FilterResult::Skip
+  }
 }
 
-impl fmt::Display for Token {
+// Parses an already-matched `<int>/<int>` slice into a ratio, rejecting a
+// zero denominator. Whitespace around the `/` keeps this regex from
+// matching at all, so it never steals from the `Div` operator token.
+fn number_ratio<'src>(lexer: &mut Lexer<'src, Token<'src>>) -> Result<Number, LexingError> {
+  let (num_str, den_str) = lexer.slice().split_once('/').expect("regex guarantees exactly one slash");
+  let invalid = || LexingError::InvalidNumber(Span::from(lexer.span()));
+  let num: u64 = num_str.parse().map_err(|_| invalid())?;
+  let den: u64 = den_str.parse().map_err(|_| invalid())?;
+  if den == 0 {
+    return Err(invalid());
+  }
+  Ok(Number::Ratio { num, den })
+}
+
+// Scans a string literal's body after the opening `"` has been matched,
+// decoding escapes as it goes, and bumps past the closing `"`. Escapes force
+// an allocation here, unlike names and comments, since the decoded text no
+// longer matches the source bytes.
+fn string_literal<'src>(lexer: &mut Lexer<'src, Token<'src>>) -> Result<String, LexingError> {
+  let quote_start = lexer.span().start as u32;
+  let rest = lexer.remainder();
+  let mut decoded = String::new();
+  let mut chars = rest.char_indices();
+  loop {
+    match chars.next() {
+      Some((i, '"')) => {
+        lexer.bump(i + 1);
+        return Ok(decoded);
+      }
+      Some((_, '\\')) => match chars.next() {
+        Some((_, escaped)) => decoded.push(match escaped {
+          'n' => '\n',
+          't' => '\t',
+          'r' => '\r',
+          '\\' => '\\',
+          '"' => '"',
+          other => other,
+        }),
+        None => {
+          lexer.bump(rest.len());
+          return Err(LexingError::UnterminatedString(Span { start: quote_start, end: lexer.span().end as u32 }));
+        }
+      },
+      Some((_, c)) => decoded.push(c),
+      None => {
+        lexer.bump(rest.len());
+        return Err(LexingError::UnterminatedString(Span { start: quote_start, end: lexer.span().end as u32 }));
+      }
+    }
+  }
+}
+
+impl fmt::Display for Token<'_> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
       Self::Name(s) => write!(f, "{}", s),
+      Self::Str(s) => {
+        write!(f, "\"")?;
+        for c in s.chars() {
+          match c {
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            '\r' => write!(f, "\\r")?,
+            '\\' => write!(f, "\\\\")?,
+            '"' => write!(f, "\\\"")?,
+            other => write!(f, "{}", other)?,
+          }
+        }
+        write!(f, "\"")
+      }
       Self::Lambda => write!(f, r"λ"),
       Self::Let => write!(f, "let"),
       Self::Dup => write!(f, "dup"),
       Self::Equals => write!(f, "="),
-      Self::Number(num) => write!(f, "{}", num.as_ref()),
+      Self::Number(num) => write!(f, "{}", num),
       Self::Add => write!(f, "+"),
       Self::Sub => write!(f, "-"),
       Self::Mul => write!(f, "*"),
@@ -172,11 +425,210 @@ impl fmt::Display for Token {
       Self::LParen => write!(f, "("),
       Self::RParen => write!(f, ")"),
       Self::NewLine => write!(f, "<NewLine>"),
-      Self::SingleLineComment => write!(f, "<SingleLineComment>"),
-      Self::MultiLineComment => write!(f, "<MultiLineComment>"),
+      Self::Comment(text) => write!(f, "<Comment {:?}>", text),
       Self::Whitespace => write!(f, "<Whitespace>"),
-      Self::Error(LexingError::InvalidCharacter) => write!(f, "<InvalidCharacter>"),
-      Self::Error(LexingError::UnclosedComment) => write!(f, "<UnclosedComment>"),
+      Self::Error(LexingError::InvalidCharacter(_)) => write!(f, "<InvalidCharacter>"),
+      Self::Error(LexingError::UnclosedComment(_)) => write!(f, "<UnclosedComment>"),
+      Self::Error(LexingError::UnterminatedString(_)) => write!(f, "<UnterminatedString>"),
+      Self::Error(LexingError::InvalidNumber(_)) => write!(f, "<InvalidNumber>"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod span_tests {
+  use super::*;
+
+  #[test]
+  fn tokens_carry_their_source_span() {
+    let tokens = lex("let x");
+    assert_eq!(tokens[0], Located { item: Token::Let, span: Span { start: 0, end: 3 } });
+    assert_eq!(tokens[1], Located { item: Token::Name("x"), span: Span { start: 4, end: 5 } });
+  }
+
+  #[test]
+  fn unclosed_nested_comment_spans_to_eof_with_no_trailing_tokens() {
+    let input = "/* outer /* inner */ still open";
+    let tokens = lex(input);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(
+      tokens[0],
+      Located {
+        item: Token::Error(LexingError::UnclosedComment(Span { start: 0, end: input.len() as u32 })),
+        span: Span { start: 0, end: input.len() as u32 },
+      }
+    );
+  }
+
+  #[test]
+  fn closed_nested_comment_resumes_scanning_after_it() {
+    let tokens = lex("/* outer /* inner */ still closed */ x");
+    let items: Vec<_> = tokens.into_iter().map(|t| t.item).collect();
+    assert_eq!(items, vec![Token::Name("x")]);
+  }
+}
+
+#[cfg(test)]
+mod string_tests {
+  use super::*;
+
+  fn lex_one(input: &str) -> Located<Token<'_>> {
+    let tokens = lex(input);
+    assert_eq!(tokens.len(), 1, "expected exactly one token from {input:?}, got {tokens:?}");
+    tokens.into_iter().next().unwrap()
+  }
+
+  #[test]
+  fn decodes_known_escapes() {
+    let Located { item, .. } = lex_one(r#""a\nb\tc\rd\\e\"f""#);
+    assert_eq!(item, Token::Str("a\nb\tc\rd\\e\"f".to_string()));
+  }
+
+  #[test]
+  fn passes_through_unknown_escapes_literally() {
+    let Located { item, .. } = lex_one(r#""\q""#);
+    assert_eq!(item, Token::Str("q".to_string()));
+  }
+
+  #[test]
+  fn display_reescapes_losslessly() {
+    let original = r#""a\nb\tc\\d\"e""#;
+    let Located { item, .. } = lex_one(original);
+    assert_eq!(item.to_string(), original);
+  }
+
+  #[test]
+  fn unterminated_string_at_eof() {
+    let Located { item, span } = lex_one(r#""abc"#);
+    assert_eq!(item, Token::Error(LexingError::UnterminatedString(Span { start: 0, end: 4 })));
+    assert_eq!(span, Span { start: 0, end: 4 });
+  }
+
+  #[test]
+  fn unterminated_string_on_trailing_backslash() {
+    let Located { item, .. } = lex_one(r#""abc\"#);
+    assert!(matches!(item, Token::Error(LexingError::UnterminatedString(_))));
+  }
+}
+
+#[cfg(test)]
+mod comment_tests {
+  use super::*;
+
+  #[test]
+  fn default_mode_discards_comments() {
+    let tokens = LexerBuilder::new().lex("// a comment\nx");
+    let items: Vec<_> = tokens.into_iter().map(|t| t.item).collect();
+    assert_eq!(items, vec![Token::NewLine, Token::Name("x")]);
+  }
+
+  #[test]
+  fn preserve_comments_emits_trimmed_single_line_body() {
+    let tokens = LexerBuilder::new().preserve_comments(true).lex("//   a comment  \nx");
+    let items: Vec<_> = tokens.into_iter().map(|t| t.item).collect();
+    assert_eq!(items, vec![Token::Comment("a comment"), Token::NewLine, Token::Name("x")]);
+  }
+
+  #[test]
+  fn preserve_comments_emits_nested_multi_line_body() {
+    let tokens = LexerBuilder::new().preserve_comments(true).lex("/* outer /* inner */ still */ x");
+    let items: Vec<_> = tokens.into_iter().map(|t| t.item).collect();
+    assert_eq!(items, vec![Token::Comment(" outer /* inner */ still "), Token::Name("x")]);
+  }
+}
+
+#[cfg(test)]
+mod number_tests {
+  use super::*;
+
+  fn lex_number(input: &str) -> Number {
+    let tokens = lex(input);
+    assert_eq!(tokens.len(), 1, "expected exactly one token from {input:?}, got {tokens:?}");
+    match tokens.into_iter().next().unwrap().item {
+      Token::Number(n) => n,
+      other => panic!("expected a Number token, got {other:?}"),
     }
   }
+
+  fn assert_round_trips(input: &str, expected: Number) {
+    let n = lex_number(input);
+    assert_eq!(n, expected);
+    assert_eq!(lex_number(&n.to_string()), expected);
+  }
+
+  #[test]
+  fn lexes_int() {
+    assert_round_trips("42", Number::Int(42));
+  }
+
+  #[test]
+  fn lexes_float() {
+    assert_round_trips("3.5", Number::Float(3.5));
+  }
+
+  #[test]
+  fn whole_number_float_round_trips_as_float() {
+    assert_round_trips("1.0", Number::Float(1.0));
+  }
+
+  #[test]
+  fn large_float_round_trips_as_float() {
+    assert_round_trips("1.0e10", Number::Float(1e10));
+  }
+
+  #[test]
+  fn lexes_exponent_without_dot() {
+    assert_round_trips("1e3", Number::Float(1e3));
+  }
+
+  #[test]
+  fn lexes_ratio() {
+    assert_round_trips("3/4", Number::Ratio { num: 3, den: 4 });
+  }
+
+  #[test]
+  fn ratio_with_zero_denominator_is_invalid() {
+    let tokens = lex("3/0");
+    assert_eq!(tokens.len(), 1);
+    assert!(matches!(tokens[0].item, Token::Error(LexingError::InvalidNumber(_))));
+  }
+
+  #[test]
+  fn division_with_whitespace_is_not_a_ratio() {
+    let tokens = lex("3 / 4");
+    let items: Vec<_> = tokens.into_iter().map(|t| t.item).collect();
+    assert_eq!(items, vec![Token::Number(Number::Int(3)), Token::Div, Token::Number(Number::Int(4))]);
+  }
+}
+
+#[cfg(test)]
+mod into_owned_tests {
+  use super::*;
+
+  #[test]
+  fn interns_name_through_the_interner() {
+    let mut interner = Interner::new();
+    let tokens = lex("foo foo bar");
+    let owned: Vec<_> = tokens.into_iter().map(|t| t.item.into_owned(&mut interner)).collect();
+    let (OwnedToken::Name(foo1), OwnedToken::Name(foo2), OwnedToken::Name(bar)) = (&owned[0], &owned[1], &owned[2])
+    else {
+      panic!("expected three Name tokens, got {owned:?}");
+    };
+    assert_eq!(foo1, foo2);
+    assert_ne!(foo1, bar);
+    assert_eq!(interner.resolve(*foo1), "foo");
+    assert_eq!(interner.resolve(*bar), "bar");
+  }
+
+  #[test]
+  fn maps_other_variants_unchanged() {
+    let mut interner = Interner::new();
+    let tokens = lex(r#"let x = 1 + "s""#);
+    let owned: Vec<_> = tokens.into_iter().map(|t| t.item.into_owned(&mut interner)).collect();
+    assert_eq!(owned[0], OwnedToken::Let);
+    assert_eq!(owned[2], OwnedToken::Equals);
+    assert_eq!(owned[3], OwnedToken::Number(Number::Int(1)));
+    assert_eq!(owned[4], OwnedToken::Add);
+    assert_eq!(owned[5], OwnedToken::Str("s".to_string()));
+  }
 }
@@ -0,0 +1,2 @@
+pub mod interner;
+pub mod lexer;